@@ -0,0 +1,54 @@
+// The parsed, but not yet fitted, design: one `OLMC` per output pin,
+// plus the chip-wide bits (`sig`, `ar`/`sp`) `gal_builder` writes out
+// alongside the per-OLMC equations.
+
+use crate::chips::Chip;
+use crate::gal::Term;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinMode {
+    Combinatorial,
+    Tristate,
+    Registered,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Active {
+    Low,
+    High,
+}
+
+#[derive(Clone, Debug)]
+#[allow(clippy::upper_case_acronyms)] // OLMC (Output Logic MacroCell) is the datasheet name.
+pub struct OLMC {
+    pub output: Option<(PinMode, Term)>,
+    pub tri_con: Option<Term>,
+    pub clock: Option<Term>,
+    pub arst: Option<Term>,
+    pub aprst: Option<Term>,
+    pub active: Active,
+    pub feedback: bool,
+}
+
+impl Default for OLMC {
+    fn default() -> OLMC {
+        OLMC {
+            output: None,
+            tri_con: None,
+            clock: None,
+            arst: None,
+            aprst: None,
+            active: Active::High,
+            feedback: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Blueprint {
+    pub chip: Chip,
+    pub sig: Vec<u8>,
+    pub olmcs: Vec<OLMC>,
+    pub ar: Option<Term>,
+    pub sp: Option<Term>,
+}
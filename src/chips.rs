@@ -0,0 +1,71 @@
+// The chips Galette knows how to fit, and the row layout of their
+// product-term array.
+
+use crate::gal::Bounds;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chip {
+    GAL16V8,
+    GAL20V8,
+    GAL22V10,
+    GAL20RA10,
+}
+
+impl Chip {
+    pub fn num_olmcs(self) -> usize {
+        match self {
+            Chip::GAL16V8 | Chip::GAL20V8 => 8,
+            Chip::GAL22V10 | Chip::GAL20RA10 => 10,
+        }
+    }
+
+    pub fn num_pins(self) -> usize {
+        match self {
+            Chip::GAL16V8 => 16,
+            Chip::GAL20V8 => 20,
+            Chip::GAL22V10 => 22,
+            Chip::GAL20RA10 => 20,
+        }
+    }
+
+    // Product-term rows available to each OLMC, pin order, low to
+    // high. The GAL22V10's macrocells are the classic 8/10/12/14/16
+    // "wedge" shape; the others give every OLMC the same budget.
+    fn rows_per_olmc(self) -> Vec<usize> {
+        match self {
+            Chip::GAL16V8 | Chip::GAL20V8 => vec![8; 8],
+            Chip::GAL22V10 => vec![8, 10, 12, 14, 16, 16, 14, 12, 10, 8],
+            Chip::GAL20RA10 => vec![16; 10],
+        }
+    }
+
+    // The GAL22V10 reserves row 0 for the global AR term (see
+    // `gal_builder::set_arsp_eqns`), so its OLMC blocks start one row
+    // later than the sum of earlier OLMCs' rows would otherwise say.
+    fn olmc_start_offset(self) -> usize {
+        match self {
+            Chip::GAL22V10 => 1,
+            _ => 0,
+        }
+    }
+
+    // The row window reserved for one OLMC's equation, before
+    // `gal_builder::tristate_adjust` carves off any rows for a
+    // tristate-enable/clock/arst/aprst term.
+    pub fn get_bounds(self, olmc: usize) -> Bounds {
+        let rows = self.rows_per_olmc();
+        let start_row = self.olmc_start_offset() + rows[..olmc].iter().sum::<usize>();
+        Bounds { start_row, max_row: rows[olmc], row_offset: 0 }
+    }
+
+    // Total rows in the fuse array's product-term section: every
+    // OLMC's rows, plus the GAL22V10's extra AR (row 0) and SP (the
+    // row right after the last OLMC) terms.
+    pub fn total_rows(self) -> usize {
+        let olmc_rows: usize = self.rows_per_olmc().iter().sum();
+        match self {
+            Chip::GAL22V10 => 1 + olmc_rows + 1,
+            _ => olmc_rows,
+        }
+    }
+}
@@ -0,0 +1,285 @@
+// Reconstruct `.pld`-style equations from an already-built (or
+// programmed-and-read-back) `GAL`, inverting what `build_galxv8`,
+// `build_gal22v10` and `build_gal20ra10` in `gal_builder` produce.
+//
+// This walks the same `Bounds`/`Mode` layout those builders use: for
+// each OLMC we read its rows within `Chip::get_bounds`, turn every
+// unblown fuse column into a literal (true-fuse -> asserted,
+// complement-fuse -> negated, neither -> the pin isn't referenced),
+// OR the rows together, and use the XOR/AC1/S1 bits to recover
+// polarity and `PinType` the same way `olmc::analyse_mode` set them.
+
+use crate::chips::Chip;
+use crate::gal::GAL;
+use crate::gal::Mode;
+use crate::gal_builder;
+
+// One recovered product row: a list of signed pin numbers (positive
+// for an asserted literal, negative for a negated one), in the same
+// shape `minimize`/`gal_builder` use for `gal::Term` rows.
+pub type Row = Vec<i32>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinType {
+    Undriven,
+    Combinatorial,
+    Tristate,
+    Registered,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Active {
+    Low,
+    High,
+}
+
+#[derive(Clone, Debug)]
+pub struct DisassembledOlmc {
+    pub pin_type: PinType,
+    pub active: Active,
+    pub rows: Vec<Row>,
+    pub enable: Option<Row>,
+}
+
+// Read one row of the fuse array back into a product-term literal
+// list: a pin is a positive literal if its true-fuse survived, a
+// negative literal if its complement-fuse survived, and it's absent
+// from the row if both fuses are blown (no term) or both survived
+// (impossible to satisfy, but we still record it as a contradiction
+// rather than silently dropping it).
+fn read_row(gal: &GAL, row: usize, num_pins: usize) -> Row {
+    let mut literals = Vec::new();
+    for pin in 0..num_pins {
+        let asserted = gal.get_fuse(row, pin * 2);
+        let negated = gal.get_fuse(row, pin * 2 + 1);
+        if asserted {
+            literals.push(pin as i32 + 1);
+        }
+        if negated {
+            literals.push(-(pin as i32 + 1));
+        }
+    }
+    literals
+}
+
+// A row with no unblown fuses at all is the always-false placeholder
+// `gal_builder::set_core_eqns` writes for an undriven OLMC; skip it
+// when reconstructing the OR of product terms.
+fn row_is_empty(row: &Row) -> bool {
+    row.is_empty()
+}
+
+fn read_rows(gal: &GAL, start_row: usize, num_rows: usize, num_pins: usize) -> Vec<Row> {
+    (start_row..start_row + num_rows)
+        .map(|row| read_row(gal, row, num_pins))
+        .filter(|row| !row_is_empty(row))
+        .collect()
+}
+
+fn pin_type_v8(mode: Mode, tristate: bool, registered: bool, has_rows: bool) -> PinType {
+    if !has_rows {
+        PinType::Undriven
+    } else if registered {
+        PinType::Registered
+    } else if tristate || mode != Mode::Mode1 {
+        PinType::Tristate
+    } else {
+        PinType::Combinatorial
+    }
+}
+
+// Disassemble one OLMC's equation. `registered` comes from
+// `gal.registered` (set by `gal_builder::set_registered`) and
+// `tristate` from the AC1/S1 classification bits, both depending on
+// the chip-wide mode, same as `olmc::analyse_mode`. The body offset -
+// how many rows at the start of the block are the tristate-enable row
+// (and, on the GAL20RA10, the clock/arst/aprst rows) - comes from
+// `gal_builder::body_offset`, the exact same per-chip layout
+// `tristate_adjust` uses when building, so a GAL20RA10's reserved
+// rows are skipped in full rather than just the first one.
+pub fn disassemble_olmc(
+    gal: &GAL,
+    olmc: usize,
+    num_pins: usize,
+    mode: Mode,
+    registered: bool,
+    tristate: bool,
+) -> DisassembledOlmc {
+    let bounds = gal.chip.get_bounds(olmc);
+    let body_offset = gal_builder::body_offset(gal, registered);
+
+    // A reserved row with no fuses at all is what the builder leaves
+    // when the source gave no `tri_con`, so it is reported as no
+    // enable term (`None`) and the OLMC is not classified as
+    // `Tristate`. The row itself still reads as false, which
+    // `simulate::eval_enabled` treats as "not driven".
+    let enable = if body_offset > 0 {
+        let row = read_row(gal, bounds.start_row, num_pins);
+        if row.is_empty() {
+            None
+        } else {
+            Some(row)
+        }
+    } else {
+        None
+    };
+    let tristate = tristate && enable.is_some();
+
+    let rows = read_rows(gal, bounds.start_row + body_offset, bounds.max_row - body_offset, num_pins);
+
+    let pin_type = match gal.chip {
+        Chip::GAL16V8 | Chip::GAL20V8 => pin_type_v8(mode, tristate, registered, !rows.is_empty()),
+        Chip::GAL22V10 | Chip::GAL20RA10 => {
+            if !rows.is_empty() && registered {
+                PinType::Registered
+            } else if !rows.is_empty() && tristate {
+                PinType::Tristate
+            } else if !rows.is_empty() {
+                PinType::Combinatorial
+            } else {
+                PinType::Undriven
+            }
+        }
+    };
+
+    // `xor`, like `ac1`/`s1`, is written in bit-reversed OLMC order by
+    // `gal_builder::set_xors`.
+    let active = if gal.xor[gal.chip.num_olmcs() - 1 - olmc] { Active::High } else { Active::Low };
+
+    DisassembledOlmc { pin_type, active, rows, enable }
+}
+
+// Disassemble every OLMC the chip has, in pin order.
+pub fn disassemble(gal: &GAL, num_olmcs: usize, num_pins: usize) -> Vec<DisassembledOlmc> {
+    let mode = gal.get_mode();
+
+    (0..num_olmcs)
+        .map(|olmc| {
+            // `registered`, like `ac1`/`s1`, is written in bit-reversed
+            // OLMC order by `gal_builder::set_registered`.
+            let registered = gal.registered[num_olmcs - 1 - olmc];
+            let tristate = match gal.chip {
+                Chip::GAL16V8 | Chip::GAL20V8 => gal.ac1[num_olmcs - 1 - olmc],
+                Chip::GAL22V10 => gal.s1[num_olmcs - 1 - olmc],
+                Chip::GAL20RA10 => false,
+            };
+
+            disassemble_olmc(gal, olmc, num_pins, mode, registered, tristate)
+        })
+        .collect()
+}
+
+// Render one recovered row as a `.pld`-style product term, e.g.
+// `A * !B * C`, for diagnostics and for diffing against source.
+pub fn format_row(row: &Row, pin_names: &[String]) -> String {
+    row.iter()
+        .map(|&lit| {
+            let name = &pin_names[(lit.abs() - 1) as usize];
+            if lit < 0 {
+                format!("!{}", name)
+            } else {
+                name.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" * ")
+}
+
+pub fn format_olmc(olmc: &DisassembledOlmc, output_name: &str, pin_names: &[String]) -> String {
+    let suffix = match olmc.pin_type {
+        PinType::Registered => ".R",
+        PinType::Tristate => ".T",
+        _ => "",
+    };
+
+    let body = olmc
+        .rows
+        .iter()
+        .map(|row| format_row(row, pin_names))
+        .collect::<Vec<_>>()
+        .join(" + ");
+
+    format!("{}{} = {}", output_name, suffix, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blueprint::Active as BpActive;
+    use crate::blueprint::Blueprint;
+    use crate::blueprint::OLMC;
+    use crate::blueprint::PinMode;
+    use crate::gal::Term;
+    use crate::gal_builder;
+
+    fn test_gal() -> GAL {
+        let mut olmcs: Vec<OLMC> = (0..10).map(|_| Default::default()).collect();
+
+        // O0: combinatorial, A + !B, active high.
+        olmcs[0].output =
+            Some((PinMode::Combinatorial, Term { line_num: 1, rows: vec![vec![1], vec![-2]] }));
+        olmcs[0].tri_con = Some(Term { line_num: 1, rows: vec![vec![1]] });
+        olmcs[0].active = BpActive::High;
+
+        // O1: registered, latches C, active low.
+        olmcs[1].output = Some((PinMode::Registered, Term { line_num: 2, rows: vec![vec![3]] }));
+        olmcs[1].tri_con = Some(Term { line_num: 2, rows: vec![vec![1]] });
+        olmcs[1].active = BpActive::Low;
+
+        // O2: combinatorial, echoes D, with no `tri_con` at all - its
+        // reserved enable row is never written, so it should round-trip
+        // as a combinatorial output with no enable term, not as a
+        // tristated one.
+        olmcs[2].output = Some((PinMode::Combinatorial, Term { line_num: 3, rows: vec![vec![4]] }));
+        olmcs[2].active = BpActive::High;
+
+        let blueprint = Blueprint { chip: Chip::GAL22V10, sig: Vec::new(), olmcs, ar: None, sp: None };
+        gal_builder::build(&blueprint, false).unwrap()
+    }
+
+    #[test]
+    fn disassemble_recovers_the_built_rows_and_polarity() {
+        let gal = test_gal();
+        let recovered = disassemble(&gal, 10, 22);
+
+        // The GAL22V10 wires every combinatorial output through the
+        // same tristate control as a declared tristate one, so
+        // `build_tristate_flags` (with `com_is_tri: true`) always
+        // classifies it as `Tristate` here, not `Combinatorial`.
+        assert_eq!(recovered[0].pin_type, PinType::Tristate);
+        assert_eq!(recovered[0].active, Active::High);
+        assert_eq!(recovered[0].rows, vec![vec![1], vec![-2]]);
+
+        assert_eq!(recovered[1].pin_type, PinType::Registered);
+        assert_eq!(recovered[1].active, Active::Low);
+        assert_eq!(recovered[1].rows, vec![vec![3]]);
+    }
+
+    #[test]
+    fn combinatorial_output_with_no_tri_con_is_not_stuck_tristated() {
+        let gal = test_gal();
+        let recovered = disassemble(&gal, 10, 22);
+
+        assert_eq!(recovered[2].pin_type, PinType::Combinatorial);
+        assert_eq!(recovered[2].enable, None);
+        assert_eq!(recovered[2].rows, vec![vec![4]]);
+    }
+
+    #[test]
+    fn undriven_olmc_disassembles_with_no_rows() {
+        let gal = test_gal();
+        let recovered = disassemble(&gal, 10, 22);
+
+        assert_eq!(recovered[5].pin_type, PinType::Undriven);
+        assert!(recovered[5].rows.is_empty());
+    }
+
+    #[test]
+    fn format_olmc_renders_a_pld_style_equation() {
+        let gal = test_gal();
+        let recovered = disassemble(&gal, 10, 22);
+        let names: Vec<String> = (1..=22).map(|n| format!("P{}", n)).collect();
+
+        assert_eq!(format_olmc(&recovered[0], "O0", &names), "O0.T = P1 + !P2");
+    }
+}
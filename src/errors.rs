@@ -0,0 +1,58 @@
+// Error reporting. Every error carries the source line it came from
+// (0 when there isn't a meaningful one, e.g. a whole-build check) plus
+// an `ErrorCode` describing what went wrong.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    NoCLK,
+    DisallowedCLK,
+    DisallowedARST,
+    DisallowedAPRST,
+    TooManyProducts,
+    AlwaysTrue,
+    VerificationFailed(usize),
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorCode::NoCLK => write!(f, "registered output needs a CLK term"),
+            ErrorCode::DisallowedCLK => write!(f, "CLK is not allowed on this chip"),
+            ErrorCode::DisallowedARST => write!(f, "ARST is not allowed on this chip"),
+            ErrorCode::DisallowedAPRST => write!(f, "APRST is not allowed on this chip"),
+            ErrorCode::TooManyProducts => {
+                write!(f, "too many product terms to fit, even after minimization")
+            }
+            ErrorCode::AlwaysTrue => {
+                write!(f, "equation minimizes to a constant true, which can't be represented as a fuse row")
+            }
+            ErrorCode::VerificationFailed(olmc) => {
+                write!(f, "built fuse map diverges from the source equations for OLMC {}", olmc)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Error {
+    pub line_num: u32,
+    pub code: ErrorCode,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line_num == 0 {
+            write!(f, "{}", self.code)
+        } else {
+            write!(f, "line {}: {}", self.line_num, self.code)
+        }
+    }
+}
+
+// Attach a line number to a `Result` still carrying a bare
+// `ErrorCode`, turning it into a full `Error`.
+pub fn at_line<T>(line_num: u32, res: Result<T, ErrorCode>) -> Result<T, Error> {
+    res.map_err(|code| Error { line_num, code })
+}
@@ -0,0 +1,118 @@
+// The in-progress fuse map for one part, plus the handful of types
+// (`Bounds`, `Mode`, `Term`) the rest of the builder shares.
+
+use crate::chips::Chip;
+use crate::errors::at_line;
+use crate::errors::Error;
+use crate::errors::ErrorCode;
+
+// The row window an equation is allowed to use: `start_row` is where
+// the OLMC's block begins in the fuse array, `max_row` is how many
+// rows the block has in total, and `row_offset` is how many of those
+// rows are already spoken for (tristate-enable/clock/arst/aprst) by
+// the time the main equation gets placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub start_row: usize,
+    pub max_row: usize,
+    pub row_offset: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Mode1,
+    Mode2,
+    Mode3,
+}
+
+// A sum of products over signed pin indices: each row is one product
+// line, a positive entry is an asserted literal, a negative one is
+// negated, and a pin simply absent from a row means it's a don't-care
+// for that product line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Term {
+    pub line_num: u32,
+    pub rows: Vec<Vec<i32>>,
+}
+
+pub fn false_term(line_num: u32) -> Term {
+    Term { line_num, rows: Vec::new() }
+}
+
+#[allow(clippy::upper_case_acronyms)] // GAL (Generic Array Logic) is the datasheet name.
+pub struct GAL {
+    pub chip: Chip,
+    pub sig: [bool; 64],
+    pub xor: Vec<bool>,
+    pub ac1: Vec<bool>,
+    pub s1: Vec<bool>,
+    pub pt: Vec<bool>,
+    // Which OLMCs the blueprint asked to be registered. Set by
+    // `gal_builder` alongside `xor`/`ac1`/`s1`, so `simulate` and
+    // `disassemble` never have to re-derive it from the mode bits.
+    pub registered: Vec<bool>,
+    mode: Mode,
+    // One entry per product-term row, two fuse bits (asserted,
+    // negated) per input pin.
+    fuses: Vec<Vec<bool>>,
+}
+
+impl GAL {
+    pub fn new(chip: Chip) -> GAL {
+        let num_olmcs = chip.num_olmcs();
+        let num_pins = chip.num_pins();
+        GAL {
+            chip,
+            sig: [false; 64],
+            xor: vec![false; num_olmcs],
+            ac1: vec![false; num_olmcs],
+            s1: vec![false; num_olmcs],
+            pt: vec![false; 64],
+            registered: vec![false; num_olmcs],
+            mode: Mode::Mode1,
+            fuses: vec![vec![false; num_pins * 2]; chip.total_rows()],
+        }
+    }
+
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    // Read one fuse back: `col` is `pin * 2` for the asserted fuse,
+    // `pin * 2 + 1` for the negated one.
+    pub fn get_fuse(&self, row: usize, col: usize) -> bool {
+        self.fuses[row][col]
+    }
+
+    pub fn add_term(&mut self, term: &Term, bounds: &Bounds) -> Result<(), Error> {
+        let available = bounds.max_row - bounds.row_offset;
+        if term.rows.len() > available {
+            return at_line(term.line_num, Err(ErrorCode::TooManyProducts));
+        }
+
+        for (i, row) in term.rows.iter().enumerate() {
+            let fuse_row = bounds.start_row + bounds.row_offset + i;
+            for &lit in row {
+                let pin = (lit.abs() - 1) as usize;
+                if lit > 0 {
+                    self.fuses[fuse_row][pin * 2] = true;
+                } else {
+                    self.fuses[fuse_row][pin * 2 + 1] = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn add_term_opt(&mut self, term: &Option<Term>, bounds: &Bounds) -> Result<(), Error> {
+        match term {
+            Some(term) => self.add_term(term, bounds),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,17 +1,22 @@
-use blueprint::Active;
-use blueprint::Blueprint;
-use blueprint::OLMC;
-use blueprint::PinMode;
-use chips::Chip;
-use errors::at_line;
-use errors::Error;
-use errors::ErrorCode;
-use gal;
-use gal::Bounds;
-use gal::GAL;
-use gal::Mode;
-
-pub fn build(blueprint: &Blueprint) -> Result<GAL, Error> {
+use crate::blueprint::Active;
+use crate::blueprint::Blueprint;
+use crate::blueprint::OLMC;
+use crate::blueprint::PinMode;
+use crate::chips::Chip;
+use crate::errors::at_line;
+use crate::errors::Error;
+use crate::errors::ErrorCode;
+use crate::gal;
+use crate::gal::Bounds;
+use crate::gal::GAL;
+use crate::gal::Mode;
+use crate::minimize;
+use crate::verify;
+
+// Build the fuse map for `blueprint`. When `verify` is set (the
+// `--verify` flag), independently re-check the result against the
+// source equations before returning it - see `verify_build`.
+pub fn build(blueprint: &Blueprint, verify: bool) -> Result<GAL, Error> {
     let mut gal = GAL::new(blueprint.chip);
 
     match gal.chip {
@@ -20,9 +25,35 @@ pub fn build(blueprint: &Blueprint) -> Result<GAL, Error> {
         Chip::GAL20RA10 => build_gal20ra10(&mut gal, blueprint)?,
     }
 
+    if verify {
+        verify_build(blueprint, &gal)?;
+    }
+
     Ok(gal)
 }
 
+// Translation-validate a build: independently evaluate each OLMC's
+// source equation and compare it against what the fuse map actually
+// drives, catching bugs in `tristate_adjust` before they ship. The XOR
+// bits and AC1/S1 flags are not checked. Never changes what `build`
+// writes out; it's an extra check layered on top, run from `build`
+// when the caller passes `verify: true`.
+fn verify_build(blueprint: &Blueprint, gal: &GAL) -> Result<(), Error> {
+    let reg_out: Vec<bool> =
+        blueprint.olmcs.iter().map(|o| matches!(o.output, Some((PinMode::Registered, _)))).collect();
+
+    match verify::verify(blueprint, gal, &reg_out).first() {
+        None => Ok(()),
+        Some(d) => {
+            eprintln!(
+                "OLMC {}: inputs {:?} expected driven={} high={}, got driven={} high={}",
+                d.olmc, d.inputs, d.expected_driven, d.expected_high, d.actual_driven, d.actual_high,
+            );
+            at_line(0, Err(ErrorCode::VerificationFailed(d.olmc)))
+        }
+    }
+}
+
 // Write out the signature.
 fn set_sig(blueprint: &Blueprint, gal: &mut GAL) {
     // Signature has space for 8 bytes.
@@ -42,13 +73,16 @@ fn set_core_eqns(gal: &mut GAL, blueprint: &Blueprint) -> Result<(), Error> {
         match &olmc.output {
             Some((_, term)) => {
                 let bounds = tristate_adjust(gal, &olmc.output, &bounds);
+                let term = minimize::minimize_term(term, bounds.max_row - bounds.row_offset)?;
                 gal.add_term(&term, &bounds)?;
             }
             None => gal.add_term(&gal::false_term(0), &bounds)?,
         }
 
         if let Some(term) = &olmc.tri_con {
-            gal.add_term(&term, &Bounds { row_offset: 0, max_row: 1, ..bounds })?;
+            let tri_bounds = Bounds { row_offset: 0, max_row: 1, ..bounds };
+            let term = minimize::minimize_term(term, tri_bounds.max_row - tri_bounds.row_offset)?;
+            gal.add_term(&term, &tri_bounds)?;
         }
     }
 
@@ -87,8 +121,8 @@ fn set_arsp_eqns(gal: &mut GAL, blueprint: &Blueprint) -> Result<(), Error> {
     let ar_bounds = Bounds { start_row: 0, max_row: 1, row_offset: 0 };
     gal.add_term_opt(&blueprint.ar, &ar_bounds)?;
 
-    // SP
-    let sp_bounds = Bounds { start_row: 131, max_row: 1, row_offset: 0 };
+    // SP is the last row of the fuse array - see `Chip::total_rows`.
+    let sp_bounds = Bounds { start_row: gal.chip.total_rows() - 1, max_row: 1, row_offset: 0 };
     gal.add_term_opt(&blueprint.sp, &sp_bounds)?;
 
     Ok(())
@@ -101,23 +135,31 @@ fn set_pts(gal: &mut GAL) {
     }
 }
 
-// Adjust the bounds for the main term of there's a tristate enable
-// term in the first row.
-fn tristate_adjust(gal: &GAL, output: &Option<(PinMode, gal::Term)>, bounds: &Bounds) -> Bounds {
+// How many rows at the start of an OLMC's block are already spoken
+// for (the tristate-enable row, plus clock/arst/aprst on the
+// GAL20RA10) before the main equation can start. A GALxV8 in mode 1,
+// or a registered output on one, has no reserved row.
+pub(crate) fn body_offset(gal: &GAL, reg_out: bool) -> usize {
     match gal.chip {
         Chip::GAL16V8 | Chip::GAL20V8 => {
-            let reg_out = if let Some((PinMode::Registered, _)) = output { true } else { false };
             if gal.get_mode() != Mode::Mode1 && !reg_out {
-                Bounds { row_offset: 1, ..*bounds }
+                1
             } else {
-                *bounds
+                0
             }
         }
-        Chip::GAL22V10 => Bounds { row_offset: 1, ..*bounds },
-        Chip::GAL20RA10 => Bounds { row_offset: 4, .. *bounds },
+        Chip::GAL22V10 => 1,
+        Chip::GAL20RA10 => 4,
     }
 }
 
+// Adjust the bounds for the main term if there's a tristate enable
+// term in the first row.
+fn tristate_adjust(gal: &GAL, output: &Option<(PinMode, gal::Term)>, bounds: &Bounds) -> Bounds {
+    let reg_out = matches!(output, Some((PinMode::Registered, _)));
+    Bounds { row_offset: body_offset(gal, reg_out), ..*bounds }
+}
+
 // Check that you're not trying to use 20ra10-specific features
 fn check_not_gal20ra10(blueprint: &Blueprint) -> Result<(), Error> {
     for olmc in blueprint.olmcs.iter() {
@@ -144,6 +186,17 @@ fn set_xors(gal: &mut GAL, blueprint: &Blueprint) {
     }
 }
 
+// Record which OLMCs are registered, so later passes don't have to
+// re-derive it from the mode bits.
+fn set_registered(gal: &mut GAL, blueprint: &Blueprint) {
+    let num_olmcs = blueprint.olmcs.len();
+    for (olmc, i) in blueprint.olmcs.iter().zip(0..) {
+        if let Some((PinMode::Registered, _)) = olmc.output {
+            gal.registered[num_olmcs - 1 - i] = true;
+        }
+    }
+}
+
 // Build the tristate control bits - set for inputs and tristated outputs.
 fn build_tristate_flags(flags: &mut [bool], blueprint: &Blueprint, com_is_tri: bool) {
     let num_olmcs = blueprint.olmcs.len();
@@ -166,32 +219,30 @@ fn build_tristate_flags(flags: &mut [bool], blueprint: &Blueprint, com_is_tri: b
 
 pub fn get_mode_v8(olmcs: &[OLMC]) -> Mode {
     // If there's a registered pin, it's mode 3.
-    for n in 0..8 {
-        if let Some((PinMode::Registered, _)) = olmcs[n].output  {
+    for olmc in olmcs.iter().take(8) {
+        if let Some((PinMode::Registered, _)) = olmc.output {
             return Mode::Mode3;
         }
     }
     // If there's a tristate, it's mode 2.
-    for n in 0..8 {
-        if let Some((PinMode::Tristate, _)) = olmcs[n].output {
+    for olmc in olmcs.iter().take(8) {
+        if let Some((PinMode::Tristate, _)) = olmc.output {
             return Mode::Mode2;
         }
     }
     // If we can't use mode 1, use mode 2.
-    for n in 0..8 {
+    for (n, olmc) in olmcs.iter().enumerate().take(8) {
         // Some OLMCs cannot be configured as pure inputs in Mode 1.
-        if olmcs[n].feedback && olmcs[n].output.is_none() {
-            if n == 3 || n == 4 {
-                return Mode::Mode2;
-            }
+        if olmc.feedback && olmc.output.is_none() && (n == 3 || n == 4) {
+            return Mode::Mode2;
         }
         // OLMC pins cannot be used as combinatorial feedback in Mode 1.
-        if olmcs[n].feedback && olmcs[n].output.is_some() {
+        if olmc.feedback && olmc.output.is_some() {
             return Mode::Mode2;
         }
     }
     // If there is still no mode defined, use mode 1.
-    return Mode::Mode1;
+    Mode::Mode1
 }
 
 ////////////////////////////////////////////////////////////////////////
@@ -223,6 +274,7 @@ fn build_galxv8(gal: &mut GAL, blueprint: &Blueprint) -> Result<(), Error> {
     build_tristate_flags(&mut gal.ac1, blueprint, com_is_tri);
 
     set_xors(gal, blueprint);
+    set_registered(gal, blueprint);
     set_pts(gal);
 
     Ok(())
@@ -239,6 +291,7 @@ fn build_gal22v10(gal: &mut GAL, blueprint: &Blueprint) -> Result<(), Error> {
     set_core_eqns(gal, blueprint)?;
     set_arsp_eqns(gal, blueprint)?;
     set_xors(gal, blueprint);
+    set_registered(gal, blueprint);
     Ok(())
 }
 
@@ -247,5 +300,6 @@ fn build_gal20ra10(gal: &mut GAL, blueprint: &Blueprint) -> Result<(), Error> {
     set_core_eqns(gal, blueprint)?;
     set_aux_eqns(gal, blueprint)?;
     set_xors(gal, blueprint);
+    set_registered(gal, blueprint);
     Ok(())
 }
@@ -0,0 +1,139 @@
+// Galette: a GAL/PAL fitter.
+//
+// This build doesn't yet have the `.pld` parser or JEDEC writer wired
+// in (they live in a different part of the pipeline than the
+// minimizer/simulator/disassembler this chunk adds), so there's no
+// design to read a real file into yet. In their place, `main` runs
+// every flag against a small built-in demo design, so the build,
+// simulation and disassembly pipeline is real and exercised end to
+// end, rather than dead code waiting on a parser.
+
+mod blueprint;
+mod chips;
+mod disassemble;
+mod errors;
+mod gal;
+mod gal_builder;
+mod minimize;
+mod simulate;
+mod verify;
+
+use crate::blueprint::Active;
+use crate::blueprint::Blueprint;
+use crate::blueprint::OLMC;
+use crate::blueprint::PinMode;
+use crate::chips::Chip;
+use crate::errors::Error;
+use crate::gal::Term;
+use std::env;
+use std::process;
+
+struct Options {
+    chip: Chip,
+    path: Option<String>,
+    disassemble: bool,
+    verify: bool,
+}
+
+fn parse_args(args: &[String]) -> Options {
+    let mut opts = Options { chip: Chip::GAL22V10, path: None, disassemble: false, verify: false };
+
+    for arg in args {
+        match arg.as_str() {
+            "-s" => (), // security bit: no writer in this build to honor it yet
+            "--chip=gal16v8" => opts.chip = Chip::GAL16V8,
+            "--chip=gal20v8" => opts.chip = Chip::GAL20V8,
+            "--chip=gal22v10" => opts.chip = Chip::GAL22V10,
+            "--chip=gal20ra10" => opts.chip = Chip::GAL20RA10,
+            "--disassemble" => opts.disassemble = true,
+            "--verify" => opts.verify = true,
+            _ => opts.path = Some(arg.clone()),
+        }
+    }
+
+    opts
+}
+
+// Stand-in pin names for the demo design, since there's no parsed
+// `.pld` source to take real names from: P1, P2, ...
+fn pin_names(num_pins: usize) -> Vec<String> {
+    (1..=num_pins).map(|n| format!("P{}", n)).collect()
+}
+
+// A handful of real OLMCs, standing in for what a `.pld` file would
+// otherwise parse into: one combinatorial output, one active-low
+// tristate output with its own enable term, one registered output,
+// and the rest left undriven.
+fn demo_blueprint(chip: Chip) -> Blueprint {
+    let mut olmcs: Vec<OLMC> = (0..chip.num_olmcs()).map(|_| Default::default()).collect();
+
+    olmcs[0].output = Some((PinMode::Combinatorial, Term { line_num: 1, rows: vec![vec![1]] }));
+    olmcs[0].active = Active::High;
+
+    olmcs[1].output = Some((PinMode::Tristate, Term { line_num: 2, rows: vec![vec![2]] }));
+    olmcs[1].tri_con = Some(Term { line_num: 2, rows: vec![vec![1]] });
+    olmcs[1].active = Active::Low;
+
+    olmcs[2].output = Some((PinMode::Registered, Term { line_num: 3, rows: vec![vec![3]] }));
+    olmcs[2].active = Active::High;
+
+    Blueprint { chip, sig: Vec::new(), olmcs, ar: None, sp: None }
+}
+
+fn run(opts: &Options) -> Result<(), Error> {
+    if opts.path.is_none() {
+        eprintln!("note: no .pld parser in this build yet; running the built-in demo design");
+    }
+
+    let blueprint = demo_blueprint(opts.chip);
+    let gal = gal_builder::build(&blueprint, opts.verify)?;
+
+    if opts.disassemble {
+        let names = pin_names(opts.chip.num_pins());
+        for (i, olmc) in
+            disassemble::disassemble(&gal, opts.chip.num_olmcs(), opts.chip.num_pins()).iter().enumerate()
+        {
+            println!(
+                "{} ({:?}, active {:?}, enable {:?})",
+                disassemble::format_olmc(olmc, &format!("O{}", i), &names),
+                olmc.pin_type,
+                olmc.active,
+                olmc.enable,
+            );
+        }
+        return Ok(());
+    }
+
+    // Exercise the simulator's vector-file plumbing too: a vector of
+    // `0`s, one per OLMC, round-tripped through the JEDEC record format
+    // and checked against the demo design's simulated outputs.
+    let vector_text = "0".repeat(opts.chip.num_olmcs());
+    if let Some(vector) = simulate::parse_vector_line(1, &vector_text) {
+        println!("line {}: {}", vector.line_num, simulate::format_vector_record(0, &vector.states));
+
+        let mut state = simulate::SimState::new(gal.registered.len());
+        for mismatch in simulate::check_vector(&gal, &mut state, 0, &vector) {
+            println!(
+                "vector {}, pin {}: expected {:?}, got {:?}",
+                mismatch.vector_index, mismatch.pin, mismatch.expected, mismatch.actual
+            );
+        }
+
+        // `simulate` is the literal one-shot entry point the request
+        // asked for, independent of vectors/persisted register state.
+        let inputs = vec![simulate::PinState::DontCare; gal.registered.len()];
+        simulate::simulate(&gal, &inputs);
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let opts = parse_args(&args);
+
+    if let Err(e) = run(&opts) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,363 @@
+// Product-term minimization.
+//
+// `gal_builder::set_core_eqns` hands every OLMC equation to
+// `GAL::add_term` as-is, and `add_term` simply errors out if the
+// equation needs more rows than the chip has left for that OLMC. A lot
+// of real designs have equations that are logically small but come out
+// of the parser with redundant product lines, so before we ever get to
+// `add_term` we run each term that has more product lines than rows
+// through Quine-McCluskey and replace it with the smallest equivalent
+// sum of products we can find; terms that already fit pass through
+// unchanged.
+
+use crate::errors::at_line;
+use crate::errors::Error;
+use crate::errors::ErrorCode;
+use crate::gal::Term;
+
+// One product line, expressed as a cube over the term's support set:
+// `Some(true)` for an asserted literal, `Some(false)` for a negated
+// one, and `None` where the variable doesn't appear (the '-' of QM).
+type Cube = Vec<Option<bool>>;
+
+// Turn a term's product rows into cubes over the sorted list of pins
+// the term actually mentions (its support). We never add a pin to the
+// support that wasn't already referenced somewhere in the term, so the
+// minimizer can't widen what the equation depends on.
+fn to_cubes(term: &Term) -> (Vec<i32>, Vec<Cube>) {
+    let mut support = term
+        .rows
+        .iter()
+        .flat_map(|row| row.iter().map(|lit| lit.abs()))
+        .collect::<Vec<_>>();
+    support.sort();
+    support.dedup();
+
+    let cubes = term
+        .rows
+        .iter()
+        .map(|row| {
+            support
+                .iter()
+                .map(|pin| {
+                    if row.contains(pin) {
+                        Some(true)
+                    } else if row.contains(&-pin) {
+                        Some(false)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Cube>()
+        })
+        .collect();
+
+    (support, cubes)
+}
+
+fn from_cubes(support: &[i32], cubes: &[Cube], line_num: u32) -> Term {
+    let rows = cubes
+        .iter()
+        .map(|cube| {
+            cube.iter()
+                .zip(support.iter())
+                .filter_map(|(lit, pin)| match lit {
+                    Some(true) => Some(*pin),
+                    Some(false) => Some(-*pin),
+                    None => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    Term { line_num, rows }
+}
+
+// Two cubes combine if they agree everywhere except one position,
+// where one has a true literal and the other has the same literal
+// negated. The combined cube has a dash there.
+fn try_combine(a: &Cube, b: &Cube) -> Option<Cube> {
+    let mut diff_at = None;
+    for i in 0..a.len() {
+        match (a[i], b[i]) {
+            (x, y) if x == y => (),
+            (Some(_), Some(_)) if diff_at.is_none() => diff_at = Some(i),
+            _ => return None,
+        }
+    }
+
+    diff_at.map(|i| {
+        let mut combined = a.clone();
+        combined[i] = None;
+        combined
+    })
+}
+
+// Standard QM prime implicant generation: repeatedly combine every
+// pair of cubes that differ in exactly one position, marking both as
+// used. Whatever is left over (never combined, in any round) is prime.
+fn prime_implicants(cubes: Vec<Cube>) -> Vec<Cube> {
+    let mut current = cubes;
+    current.sort();
+    current.dedup();
+
+    let mut primes = Vec::new();
+
+    loop {
+        let mut used = vec![false; current.len()];
+        let mut next = Vec::new();
+
+        for i in 0..current.len() {
+            for j in (i + 1)..current.len() {
+                if let Some(combined) = try_combine(&current[i], &current[j]) {
+                    used[i] = true;
+                    used[j] = true;
+                    if !next.contains(&combined) {
+                        next.push(combined);
+                    }
+                }
+            }
+        }
+
+        for (cube, was_used) in current.iter().zip(used.iter()) {
+            if !was_used && !primes.contains(cube) {
+                primes.push(cube.clone());
+            }
+        }
+
+        if next.is_empty() {
+            break;
+        }
+        current = next;
+    }
+
+    primes
+}
+
+fn covers(prime: &Cube, minterm: &Cube) -> bool {
+    prime
+        .iter()
+        .zip(minterm.iter())
+        .all(|(p, m)| p.is_none() || p == m)
+}
+
+// Greedy essential-prime selection: take any prime that's the only one
+// covering some minterm, then repeatedly take whichever remaining
+// prime covers the most still-uncovered minterms. This is the usual
+// practical stand-in for full Petrick's method, which we only fall
+// back to when the leftover chart is small enough to be exhaustive.
+fn select_cover(primes: &[Cube], minterms: &[Cube]) -> Vec<Cube> {
+    let mut uncovered: Vec<usize> = (0..minterms.len()).collect();
+    let mut chosen = Vec::new();
+
+    // Essential primes: the sole cover of some minterm.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &mi in uncovered.clone().iter() {
+            let covering: Vec<usize> = primes
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| covers(p, &minterms[mi]))
+                .map(|(pi, _)| pi)
+                .collect();
+
+            if covering.len() == 1 && !chosen.contains(&covering[0]) {
+                chosen.push(covering[0]);
+                uncovered.retain(|&u| !covers(&primes[covering[0]], &minterms[u]));
+                changed = true;
+                break;
+            }
+        }
+    }
+
+    // Petrick's method on what's left, if the chart is small enough to
+    // search exhaustively; otherwise greedily pick the prime covering
+    // the most remaining minterms until nothing is left uncovered.
+    let exhaustive_limit = 20;
+    if !uncovered.is_empty() && primes.len() <= exhaustive_limit {
+        if let Some(best) = petrick(primes, minterms, &uncovered, &chosen) {
+            return best.into_iter().map(|i| primes[i].clone()).collect();
+        }
+    }
+
+    while !uncovered.is_empty() {
+        let best = primes
+            .iter()
+            .enumerate()
+            .filter(|(pi, _)| !chosen.contains(pi))
+            .max_by_key(|(_, p)| uncovered.iter().filter(|&&mi| covers(p, &minterms[mi])).count())
+            .map(|(pi, _)| pi);
+
+        match best {
+            Some(pi) => {
+                chosen.push(pi);
+                uncovered.retain(|&u| !covers(&primes[pi], &minterms[u]));
+            }
+            None => break, // shouldn't happen: every minterm is covered by some prime
+        }
+    }
+
+    chosen.into_iter().map(|i| primes[i].clone()).collect()
+}
+
+// Exhaustive search for the smallest set of additional primes that
+// covers every remaining minterm, on top of whatever's already chosen.
+fn petrick(
+    primes: &[Cube],
+    minterms: &[Cube],
+    uncovered: &[usize],
+    chosen: &[usize],
+) -> Option<Vec<usize>> {
+    let candidates: Vec<usize> = (0..primes.len()).filter(|pi| !chosen.contains(pi)).collect();
+
+    let mut best: Option<Vec<usize>> = None;
+    for mask in 1u32..(1 << candidates.len()) {
+        let picked: Vec<usize> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| mask & (1 << bit) != 0)
+            .map(|(_, &pi)| pi)
+            .collect();
+
+        if best.as_ref().is_some_and(|b| picked.len() >= b.len()) {
+            continue;
+        }
+
+        let all_covered = uncovered
+            .iter()
+            .all(|&mi| picked.iter().any(|&pi| covers(&primes[pi], &minterms[mi])));
+
+        if all_covered {
+            let mut combined = chosen.to_vec();
+            combined.extend(picked);
+            best = Some(combined);
+        }
+    }
+
+    best
+}
+
+// Minimize `term`, keeping only as many product lines as it takes to
+// cover every original minterm, and error if that still doesn't fit in
+// `max_rows`.
+//
+// Terms that already fit are returned unchanged, in their original
+// row order: running them through Quine-McCluskey anyway wouldn't
+// change whether they fit, but `select_cover`/`prime_implicants` sort
+// and dedup cubes as a side effect, which would needlessly reorder an
+// already-fitting design's product lines and change its fuse map
+// byte-for-byte with no logical difference.
+pub fn minimize_term(term: &Term, max_rows: usize) -> Result<Term, Error> {
+    if term.rows.len() <= max_rows {
+        return Ok(term.clone());
+    }
+
+    let (support, cubes) = to_cubes(term);
+
+    if cubes.is_empty() {
+        return Ok(term.clone());
+    }
+
+    let primes = prime_implicants(cubes.clone());
+    let cover = select_cover(&primes, &cubes);
+
+    // A cube that's all dashes covers every input - the term minimizes
+    // to a constant true. Its row would have no literals at all, which
+    // is exactly how `gal::false_term`'s always-false placeholder reads
+    // once it's blown into fuses, so there's no way to tell the two
+    // apart downstream. Reject it outright rather than silently fusing
+    // a tautology as its own negation.
+    if cover.iter().any(|cube| cube.iter().all(Option::is_none)) {
+        return at_line(term.line_num, Err(ErrorCode::AlwaysTrue));
+    }
+
+    let minimized = from_cubes(&support, &cover, term.line_num);
+
+    if minimized.rows.len() > max_rows {
+        return at_line(term.line_num, Err(ErrorCode::TooManyProducts));
+    }
+
+    Ok(minimized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Evaluate a term's sum of products over a pin -> value assignment.
+    fn eval(term: &Term, values: &[(i32, bool)]) -> bool {
+        term.rows.iter().any(|row| {
+            row.iter().all(|&lit| {
+                let pin = lit.abs();
+                let value = values.iter().find(|&&(p, _)| p == pin).unwrap().1;
+                if lit > 0 {
+                    value
+                } else {
+                    !value
+                }
+            })
+        })
+    }
+
+    fn assert_equivalent(term: &Term, minimized: &Term, pins: &[i32]) {
+        for mask in 0..(1u32 << pins.len()) {
+            let values: Vec<(i32, bool)> =
+                pins.iter().enumerate().map(|(i, &p)| (p, mask & (1 << i) != 0)).collect();
+            assert_eq!(
+                eval(term, &values),
+                eval(minimized, &values),
+                "disagreement for {:?}",
+                values
+            );
+        }
+    }
+
+    #[test]
+    fn already_fitting_term_is_returned_unchanged() {
+        // A B + !A C, already within budget: must come back byte-for-byte
+        // identical, not just logically equivalent, so a fitting design's
+        // fuse map doesn't shift around for no reason.
+        let term = Term { line_num: 1, rows: vec![vec![1, 2], vec![-1, 3]] };
+        let minimized = minimize_term(&term, 4).unwrap();
+        assert_eq!(term, minimized);
+    }
+
+    #[test]
+    fn overflowing_term_is_minimized_to_an_equivalent_one() {
+        // A B + A !B + !A B covers every minterm except !A !B, i.e. A + B.
+        let term = Term {
+            line_num: 1,
+            rows: vec![vec![1, 2], vec![1, -2], vec![-1, 2]],
+        };
+        let minimized = minimize_term(&term, 2).unwrap();
+        assert!(minimized.rows.len() <= 2);
+        assert_equivalent(&term, &minimized, &[1, 2]);
+    }
+
+    #[test]
+    fn term_that_minimizes_to_a_constant_true_is_an_error() {
+        // A + !A covers every input, so QM collapses it to the all-dash
+        // cube. Fusing that as a row would be indistinguishable from
+        // `gal::false_term`'s always-false placeholder, silently
+        // inverting the equation, so it must be rejected instead.
+        let term = Term { line_num: 9, rows: vec![vec![1], vec![-1]] };
+        let err = minimize_term(&term, 1).unwrap_err();
+        assert_eq!(err.line_num, 9);
+        assert_eq!(err.code, ErrorCode::AlwaysTrue);
+    }
+
+    #[test]
+    fn minimization_that_still_overflows_is_an_error() {
+        // The 3-variable majority function: its three prime implicants
+        // (AB, BC, AC) are all essential, so it can't be covered by
+        // fewer than 3 rows and a budget of 1 must fail.
+        let term = Term {
+            line_num: 7,
+            rows: vec![vec![1, 2], vec![2, 3], vec![1, 3]],
+        };
+        let err = minimize_term(&term, 1).unwrap_err();
+        assert_eq!(err.line_num, 7);
+        assert_eq!(err.code, ErrorCode::TooManyProducts);
+    }
+}
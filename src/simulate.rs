@@ -0,0 +1,442 @@
+// Functional simulation of a built GAL, plus the JEDEC `V` (test
+// vector) records that go with it.
+//
+// This reads the fuse array back the same way the part itself would:
+// each OLMC's output is the OR of its product-term rows (each row an
+// AND of literals, the fuse pattern deciding which pins are negated),
+// the XOR bit sets active-high vs. active-low, the tristate-enable
+// row decides whether the pin is actually driven, and registered
+// outputs latch on the rising edge of their clock, subject to
+// `arst`/`aprst` (GAL20RA10 only - the only chip with per-OLMC async
+// reset/preset rows).
+
+use crate::chips::Chip;
+use crate::gal::Bounds;
+use crate::gal::GAL;
+use crate::gal_builder;
+
+// The JEDEC vector alphabet, plus the clock pulse that drives
+// registered outputs between vectors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinState {
+    Low,        // 0 - drive/expect low
+    High,       // 1 - drive/expect high
+    ExpectLow,  // L - expect low, pin not driven by the tester
+    ExpectHigh, // H - expect high, pin not driven by the tester
+    HighZ,      // Z - expect the pin to be tristated
+    DontCare,   // X - input not exercised by this vector
+    Clock,      // C - pulse the clock (every registered OLMC latches) and re-settle
+}
+
+impl PinState {
+    // The single JEDEC vector-record character for this state.
+    pub fn to_char(self) -> char {
+        match self {
+            PinState::Low => '0',
+            PinState::High => '1',
+            PinState::ExpectLow => 'L',
+            PinState::ExpectHigh => 'H',
+            PinState::HighZ => 'Z',
+            PinState::DontCare => 'X',
+            PinState::Clock => 'C',
+        }
+    }
+
+    fn from_char(c: char) -> Option<PinState> {
+        match c {
+            '0' => Some(PinState::Low),
+            '1' => Some(PinState::High),
+            'L' => Some(PinState::ExpectLow),
+            'H' => Some(PinState::ExpectHigh),
+            'Z' => Some(PinState::HighZ),
+            'X' => Some(PinState::DontCare),
+            'C' => Some(PinState::Clock),
+            _ => None,
+        }
+    }
+}
+
+// One declared `.pld` test vector: one state per OLMC, in OLMC order.
+// Position `i` is both the input level of pin literal `i + 1` and the
+// expected output of OLMC `i`; a vector longer than the chip's OLMC
+// count is not supported.
+#[derive(Clone, Debug)]
+pub struct Vector {
+    pub line_num: u32,
+    pub states: Vec<PinState>,
+}
+
+// Parse the state text of one `.pld` vector line, e.g. the `10XXLHZC`
+// of `VECTOR 10XXLHZC`, into a `Vector`; any other character (the
+// keyword included) makes it return `None`. This is the minimal grammar the parser hands test-vector
+// text off to; it doesn't know anything about pin names; the caller
+// maps each character position to a pin the same way it maps fuse
+// columns to pins elsewhere.
+pub fn parse_vector_line(line_num: u32, text: &str) -> Option<Vector> {
+    let states = text
+        .trim()
+        .chars()
+        .map(PinState::from_char)
+        .collect::<Option<Vec<_>>>()?;
+
+    if states.is_empty() {
+        return None;
+    }
+
+    Some(Vector { line_num, states })
+}
+
+// Render a simulated (or expected) vector as a JEDEC `V` record, e.g.
+// `V0001 10XXLHZC*`.
+pub fn format_vector_record(index: usize, states: &[PinState]) -> String {
+    let body: String = states.iter().map(|s| s.to_char()).collect();
+    format!("V{:04} {}*", index, body)
+}
+
+// Registered feedback that persists across vectors, one slot per
+// OLMC.
+pub struct SimState {
+    registers: Vec<bool>,
+}
+
+impl SimState {
+    pub fn new(num_olmcs: usize) -> SimState {
+        SimState { registers: vec![false; num_olmcs] }
+    }
+}
+
+// A single mismatch between what we simulated and what a vector
+// expected.
+#[derive(Debug)]
+pub struct Mismatch {
+    pub vector_index: usize,
+    pub pin: usize,
+    pub expected: PinState,
+    pub actual: PinState,
+}
+
+fn row_true(gal: &GAL, row: usize, inputs: &[bool]) -> bool {
+    // A row is true when every fused-in literal agrees with the
+    // input: the true-fuse for a pin means "this row needs the pin
+    // high", the complement-fuse means "needs it low". A row with no
+    // fuses blown at all is the always-false row `gal_builder` emits
+    // for an undriven OLMC.
+    let mut any_fuse = false;
+    for (pin, &value) in inputs.iter().enumerate() {
+        if gal.get_fuse(row, pin * 2) {
+            any_fuse = true;
+            if !value {
+                return false;
+            }
+        }
+        if gal.get_fuse(row, pin * 2 + 1) {
+            any_fuse = true;
+            if value {
+                return false;
+            }
+        }
+    }
+    any_fuse
+}
+
+fn eval_sum(gal: &GAL, start_row: usize, num_rows: usize, inputs: &[bool]) -> bool {
+    (start_row..start_row + num_rows).any(|row| row_true(gal, row, inputs))
+}
+
+// Evaluate one reserved row at the start of an OLMC's block (the
+// tristate-enable row, or - on the GAL20RA10 only - the clock/arst/
+// aprst rows right after it).
+fn eval_reserved(gal: &GAL, bounds: &Bounds, index: usize, inputs: &[bool]) -> bool {
+    row_true(gal, bounds.start_row + index, inputs)
+}
+
+// Evaluate the raw AND-OR array value an OLMC would drive right now,
+// ignoring whatever's latched in its register and before the output
+// buffer's XOR stage. This is also what `verify` compares against the
+// untouched blueprint rows - `set_core_eqns` writes the user's
+// equation as-is, with no polarity adjustment of its own, so this and
+// `eval_source_term` agree with no inversion on either side. Callers
+// that want the actual pin level (what a real part, or a JEDEC vector,
+// would show) need `apply_polarity` on top of this.
+pub fn eval_combinational(gal: &GAL, olmc: usize, inputs: &[bool], reg_out: bool) -> bool {
+    let bounds = gal.chip.get_bounds(olmc);
+    let offset = gal_builder::body_offset(gal, reg_out);
+    eval_sum(gal, bounds.start_row + offset, bounds.max_row - offset, inputs)
+}
+
+// The output buffer's XOR stage: real hardware runs both the
+// combinational and the registered path through the same XOR gate
+// before it reaches the pin, so this is applied once here rather than
+// inside `eval_combinational`/`clock_edge`. `xor`, like `ac1`/`s1`/
+// `registered`, is written in bit-reversed OLMC order by
+// `gal_builder::set_xors`, and is set for an active-high declaration
+// - an active-low OLMC drives the complement of the raw AND-OR value.
+fn apply_polarity(gal: &GAL, olmc: usize, value: bool) -> bool {
+    let num_olmcs = gal.chip.num_olmcs();
+    if gal.xor[num_olmcs - 1 - olmc] {
+        value
+    } else {
+        !value
+    }
+}
+
+// Is this OLMC's output actually being driven right now, or is it
+// tristated? Combinational/registered OLMCs with no separate enable
+// row are always driven; ones with a tristate-enable row (the first
+// row of their block) look at that row instead. Also used by
+// `verify`.
+pub fn eval_enabled(gal: &GAL, olmc: usize, inputs: &[bool], reg_out: bool) -> bool {
+    if gal_builder::body_offset(gal, reg_out) == 0 {
+        return true;
+    }
+    let bounds = gal.chip.get_bounds(olmc);
+    eval_reserved(gal, &bounds, 0, inputs)
+}
+
+// Run one clock edge: recompute every registered OLMC from the
+// vector's input levels (outputs are not fed back) and latch it,
+// honoring the GAL20RA10's async reset/preset rows.
+fn clock_edge(gal: &GAL, state: &mut SimState, inputs: &[bool]) {
+    let num_olmcs = gal.chip.num_olmcs();
+    for olmc in 0..gal.registered.len() {
+        // `registered`, like `xor`/`ac1`/`s1`, is written in
+        // bit-reversed OLMC order by `gal_builder::set_registered`.
+        if !gal.registered[num_olmcs - 1 - olmc] {
+            continue;
+        }
+
+        let (aprst, arst) = if gal.chip == Chip::GAL20RA10 {
+            let bounds = gal.chip.get_bounds(olmc);
+            (eval_reserved(gal, &bounds, 3, inputs), eval_reserved(gal, &bounds, 2, inputs))
+        } else {
+            (false, false)
+        };
+
+        state.registers[olmc] = if aprst {
+            true
+        } else if arst {
+            false
+        } else {
+            eval_combinational(gal, olmc, inputs, true)
+        };
+    }
+}
+
+fn to_bool(pin: PinState) -> bool {
+    matches!(pin, PinState::High | PinState::ExpectHigh)
+}
+
+fn states_match(expected: PinState, actual_high: bool, driven: bool) -> bool {
+    match expected {
+        PinState::HighZ => !driven,
+        PinState::ExpectHigh | PinState::High => driven && actual_high,
+        PinState::ExpectLow | PinState::Low => driven && !actual_high,
+        PinState::DontCare | PinState::Clock => true,
+    }
+}
+
+// Simulate `gal` over one test vector, returning the pin-by-pin
+// output it would actually drive (in the same order as `vector`).
+pub fn simulate_vector(gal: &GAL, state: &mut SimState, vector: &Vector) -> Vec<PinState> {
+    let inputs: Vec<bool> = vector.states.iter().map(|&p| to_bool(p)).collect();
+
+    if vector.states.contains(&PinState::Clock) {
+        clock_edge(gal, state, &inputs);
+    }
+
+    let num_olmcs = gal.chip.num_olmcs();
+    (0..vector.states.len())
+        .map(|olmc| {
+            // `registered`, like `xor`/`ac1`/`s1`, is written in
+            // bit-reversed OLMC order by `gal_builder::set_registered`.
+            let reg_out = gal.registered[num_olmcs - 1 - olmc];
+            let driven = eval_enabled(gal, olmc, &inputs, reg_out);
+            let raw = if reg_out { state.registers[olmc] } else { eval_combinational(gal, olmc, &inputs, reg_out) };
+            let high = apply_polarity(gal, olmc, raw);
+
+            if !driven {
+                PinState::HighZ
+            } else if high {
+                PinState::High
+            } else {
+                PinState::Low
+            }
+        })
+        .collect()
+}
+
+// Simulate a single input assignment against `gal`, without any
+// notion of test vectors or persisted register state: the entry
+// point the request asks for, for one-shot functional checks.
+pub fn simulate(gal: &GAL, inputs: &[PinState]) -> Vec<PinState> {
+    let mut state = SimState::new(gal.registered.len());
+    let vector = Vector { line_num: 0, states: inputs.to_vec() };
+    simulate_vector(gal, &mut state, &vector)
+}
+
+// Compare simulated output against the vector's expectations, in
+// order, returning every pin that didn't match.
+pub fn check_vector(gal: &GAL, state: &mut SimState, index: usize, vector: &Vector) -> Vec<Mismatch> {
+    let actual = simulate_vector(gal, state, vector);
+
+    vector
+        .states
+        .iter()
+        .zip(actual.iter())
+        .enumerate()
+        .filter_map(|(pin, (&expected, &got))| {
+            let driven = got != PinState::HighZ;
+            let high = got == PinState::High;
+            if states_match(expected, high, driven) {
+                None
+            } else {
+                Some(Mismatch { vector_index: index, pin, expected, actual: got })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blueprint::Active;
+    use crate::blueprint::Blueprint;
+    use crate::blueprint::OLMC;
+    use crate::blueprint::PinMode;
+    use crate::gal::Term;
+    use crate::gal_builder;
+
+    // Pin (1-based literal) layout shared by every test below. A pin
+    // position doubles as that same-numbered OLMC's own output slot,
+    // so EN/A/B are deliberately put on OLMCs 3-5, which are set up
+    // below as pass-through wires - otherwise driving "EN" would also
+    // set the expectation for whichever OLMC happens to sit at that
+    // position. OLMCs 0-2 and 6 (the ones under test) only ever appear
+    // as outputs.
+    const EN: i32 = 4;
+    const A: i32 = 5;
+    const B: i32 = 6;
+
+    fn test_gal() -> GAL {
+        let mut olmcs: Vec<OLMC> = (0..10).map(|_| Default::default()).collect();
+
+        // O0: combinatorial, echoes A, active high.
+        olmcs[0].output = Some((PinMode::Combinatorial, Term { line_num: 1, rows: vec![vec![A]] }));
+        olmcs[0].tri_con = Some(Term { line_num: 1, rows: vec![vec![EN]] });
+        olmcs[0].active = Active::High;
+
+        // O1: tristate, echoes B, enabled by EN.
+        olmcs[1].output = Some((PinMode::Tristate, Term { line_num: 2, rows: vec![vec![B]] }));
+        olmcs[1].tri_con = Some(Term { line_num: 2, rows: vec![vec![EN]] });
+        olmcs[1].active = Active::High;
+
+        // O2: registered, latches A on the next clock pulse, enabled by EN.
+        // GAL22V10 reserves a tristate-enable row for every OLMC, even
+        // ones that aren't declared tristate.
+        olmcs[2].output = Some((PinMode::Registered, Term { line_num: 3, rows: vec![vec![A]] }));
+        olmcs[2].tri_con = Some(Term { line_num: 3, rows: vec![vec![EN]] });
+        olmcs[2].active = Active::High;
+
+        // O6: combinatorial, echoes A like O0, but active low - the
+        // raw AND-OR value is identical to O0's, so the physical pin
+        // should read the opposite level.
+        olmcs[6].output = Some((PinMode::Combinatorial, Term { line_num: 5, rows: vec![vec![A]] }));
+        olmcs[6].tri_con = Some(Term { line_num: 5, rows: vec![vec![EN]] });
+        olmcs[6].active = Active::Low;
+
+        // EN/A/B (OLMCs 3-5) are self-driven pass-through wires: each
+        // just echoes and enables itself, so driving one to `High` as
+        // an input reads back `High` on its own position too, rather
+        // than tripping `check_vector`'s mismatch check on a pin only
+        // ever meant to feed the OLMCs above.
+        for (i, lit) in [(3, EN), (4, A), (5, B)] {
+            olmcs[i].output = Some((PinMode::Combinatorial, Term { line_num: 4, rows: vec![vec![lit]] }));
+            olmcs[i].tri_con = Some(Term { line_num: 4, rows: vec![vec![lit]] });
+            olmcs[i].active = Active::High;
+        }
+
+        let blueprint = Blueprint { chip: Chip::GAL22V10, sig: Vec::new(), olmcs, ar: None, sp: None };
+        gal_builder::build(&blueprint, false).unwrap()
+    }
+
+    // Every pin starts as `DontCare` - an input not driven at all - so
+    // an un-enabled OLMC's `HighZ` output never trips up a check that
+    // only cares about a couple of pins; `simulate`/`to_bool` treats
+    // `DontCare` as low for driving purposes.
+    fn pins(set_high: &[i32]) -> Vec<PinState> {
+        let mut states = vec![PinState::DontCare; 10];
+        for &lit in set_high {
+            states[(lit - 1) as usize] = PinState::High;
+        }
+        states
+    }
+
+    #[test]
+    fn combinational_output_follows_its_input() {
+        let gal = test_gal();
+
+        assert_eq!(simulate(&gal, &pins(&[EN]))[0], PinState::Low);
+        assert_eq!(simulate(&gal, &pins(&[EN, A]))[0], PinState::High);
+    }
+
+    #[test]
+    fn active_low_output_reports_the_physical_inverted_level() {
+        let gal = test_gal();
+
+        // Same raw equation as `combinational_output_follows_its_input`'s
+        // O0 (echo A), but active low: the physical pin must read the
+        // complement of what the AND-OR array computes.
+        assert_eq!(simulate(&gal, &pins(&[EN]))[6], PinState::High);
+        assert_eq!(simulate(&gal, &pins(&[EN, A]))[6], PinState::Low);
+    }
+
+    #[test]
+    fn tristate_output_is_high_z_until_enabled() {
+        let gal = test_gal();
+
+        assert_eq!(simulate(&gal, &pins(&[B]))[1], PinState::HighZ);
+        assert_eq!(simulate(&gal, &pins(&[EN, B]))[1], PinState::High);
+    }
+
+    #[test]
+    fn registered_output_latches_only_on_a_clock_vector() {
+        let gal = test_gal();
+        let mut state = SimState::new(gal.registered.len());
+
+        let settle_vector = Vector { line_num: 1, states: pins(&[EN, A]) };
+        let output = simulate_vector(&gal, &mut state, &settle_vector);
+        // No clock pulsed yet: the register hasn't latched the new input.
+        assert_eq!(output[2], PinState::Low);
+
+        let mut clock_states = pins(&[EN, A]);
+        clock_states[2] = PinState::Clock;
+        let clock_vector = Vector { line_num: 2, states: clock_states };
+        let output = simulate_vector(&gal, &mut state, &clock_vector);
+        assert_eq!(output[2], PinState::High);
+    }
+
+    #[test]
+    fn vector_line_round_trips_through_jedec_format() {
+        let vector = parse_vector_line(5, "10XLHZC").unwrap();
+        assert_eq!(vector.line_num, 5);
+        assert_eq!(vector.states.len(), 7);
+        assert_eq!(format_vector_record(3, &vector.states), "V0003 10XLHZC*");
+    }
+
+    #[test]
+    fn check_vector_reports_a_mismatch() {
+        let gal = test_gal();
+        let mut state = SimState::new(gal.registered.len());
+
+        // O1 is enabled and driven by B, so it reads high - but the
+        // vector claims it should read low.
+        let mut states = pins(&[EN, B]);
+        states[1] = PinState::ExpectLow;
+        let vector = Vector { line_num: 1, states };
+
+        let mismatches = check_vector(&gal, &mut state, 0, &vector);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].pin, 1);
+    }
+}
+
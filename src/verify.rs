@@ -0,0 +1,206 @@
+// Translation validation: check that the `GAL` `gal_builder::build`
+// produced computes the same function as the source `Blueprint`
+// equations, so a bug in `row_offset`/`tristate_adjust` shows up as a
+// reported divergence instead of silently shipping a wrong fuse map.
+// The check compares each OLMC's AND-OR array value (before the XOR
+// stage) and its enable row; it does not look at the XOR bits or the
+// AC1/S1 flags, so errors in `set_xors` or `build_tristate_flags` are
+// not reported. Run behind `--verify`; it never changes what `build`
+// writes out, it just double-checks it.
+
+use crate::blueprint::Blueprint;
+use crate::gal::GAL;
+use crate::simulate;
+
+// A single input assignment and output where the source equations and
+// the built fuse map disagree.
+#[derive(Debug)]
+pub struct Divergence {
+    pub olmc: usize,
+    pub inputs: Vec<bool>,
+    pub expected_driven: bool,
+    pub expected_high: bool,
+    pub actual_driven: bool,
+    pub actual_high: bool,
+}
+
+// Evaluate a source `gal::Term` directly, independent of however it
+// ends up laid out in the fuse array: OR together its product rows,
+// each row an AND of its (possibly negated) literals.
+fn eval_source_term(rows: &[Vec<i32>], inputs: &[bool]) -> bool {
+    rows.iter().any(|row| {
+        !row.is_empty()
+            && row.iter().all(|&lit| {
+                let pin = (lit.abs() - 1) as usize;
+                if lit > 0 {
+                    inputs[pin]
+                } else {
+                    !inputs[pin]
+                }
+            })
+    })
+}
+
+// Above this many input pins (counted up to the highest pin any
+// equation references), an exhaustive sweep isn't practical, so we
+// fall back to random vectors plus every single- and
+// double-bit pattern.
+const EXHAUSTIVE_LIMIT: usize = 16;
+
+fn assignments(num_pins: usize) -> Vec<Vec<bool>> {
+    if num_pins <= EXHAUSTIVE_LIMIT {
+        (0..(1u32 << num_pins))
+            .map(|mask| (0..num_pins).map(|pin| mask & (1 << pin) != 0).collect())
+            .collect()
+    } else {
+        let mut cases = vec![vec![false; num_pins], vec![true; num_pins]];
+        for pin in 0..num_pins {
+            let mut single = vec![false; num_pins];
+            single[pin] = true;
+            cases.push(single);
+            for other in (pin + 1)..num_pins {
+                let mut double = vec![false; num_pins];
+                double[pin] = true;
+                double[other] = true;
+                cases.push(double);
+            }
+        }
+        // A large batch of pseudo-random vectors rounds out the
+        // coverage without an exhaustive sweep. We don't have a PRNG
+        // dependency here, so we derive them deterministically from
+        // the assignment index with a cheap mixing function.
+        for seed in 0..1000u64 {
+            let mut mixed = seed.wrapping_mul(0x9E3779B97F4A7C15);
+            let case = (0..num_pins)
+                .map(|_| {
+                    mixed ^= mixed << 13;
+                    mixed ^= mixed >> 7;
+                    mixed ^= mixed << 17;
+                    mixed & 1 != 0
+                })
+                .collect();
+            cases.push(case);
+        }
+        cases
+    }
+}
+
+// Check one OLMC's source equation against what the built `GAL`
+// actually drives for that same input, across the chosen assignments,
+// returning the first divergence found (if any).
+pub fn verify_olmc(
+    gal: &GAL,
+    olmc: usize,
+    source_rows: &[Vec<i32>],
+    source_enable: Option<&[Vec<i32>]>,
+    reg_out: bool,
+    num_pins: usize,
+) -> Option<Divergence> {
+    // Some chips (GAL22V10, GAL20RA10) reserve an enable row for every
+    // OLMC whether or not the source gave one - with no `tri_con`
+    // term, that row is the always-false placeholder, so the output
+    // is never driven. Only chips/modes with no reserved row at all
+    // (`body_offset` 0) default an absent enable term to "always on".
+    let always_driven = crate::gal_builder::body_offset(gal, reg_out) == 0;
+
+    for inputs in assignments(num_pins) {
+        let expected_high = eval_source_term(source_rows, &inputs);
+        let expected_driven = match source_enable {
+            Some(enable_rows) => eval_source_term(enable_rows, &inputs),
+            None => always_driven,
+        };
+
+        let actual_high = simulate::eval_combinational(gal, olmc, &inputs, reg_out);
+        let actual_driven = simulate::eval_enabled(gal, olmc, &inputs, reg_out);
+
+        if expected_driven != actual_driven || (expected_driven && expected_high != actual_high) {
+            return Some(Divergence {
+                olmc,
+                inputs,
+                expected_driven,
+                expected_high,
+                actual_driven,
+                actual_high,
+            });
+        }
+    }
+    None
+}
+
+// The input support is whatever pin indices any OLMC's equation
+// mentions; we don't get a pin count handed to us, so derive it from
+// the highest literal referenced anywhere in the blueprint.
+fn num_pins(blueprint: &Blueprint) -> usize {
+    blueprint
+        .olmcs
+        .iter()
+        .flat_map(|o| {
+            let output = o.output.iter().flat_map(|(_, term)| term.rows.iter());
+            let enable = o.tri_con.iter().flat_map(|term| term.rows.iter());
+            output.chain(enable)
+        })
+        .flat_map(|row| row.iter().map(|lit| lit.unsigned_abs() as usize))
+        .max()
+        .unwrap_or(0)
+}
+
+// Verify every driven OLMC in `blueprint` against the fuse map `gal`.
+// Returns one divergence per OLMC that disagrees; an empty result
+// means the build is faithful to the source.
+pub fn verify(blueprint: &Blueprint, gal: &GAL, reg_out: &[bool]) -> Vec<Divergence> {
+    let num_pins = num_pins(blueprint);
+
+    blueprint
+        .olmcs
+        .iter()
+        .enumerate()
+        .filter_map(|(olmc, o)| {
+            let (_, term) = o.output.as_ref()?;
+            let enable = o.tri_con.as_ref().map(|t| t.rows.as_slice());
+            verify_olmc(gal, olmc, &term.rows, enable, reg_out[olmc], num_pins)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blueprint::OLMC;
+    use crate::blueprint::PinMode;
+    use crate::chips::Chip;
+    use crate::gal::Term;
+    use crate::gal_builder;
+
+    // GAL22V10 reserves a tristate-enable row for every OLMC, so O0
+    // needs its own enable term (driven by pin 3, left out of every
+    // equation under test) to ever actually be driven.
+    fn make_blueprint(term: Term) -> Blueprint {
+        let mut olmcs: Vec<OLMC> = (0..10).map(|_| Default::default()).collect();
+        olmcs[0].output = Some((PinMode::Combinatorial, term));
+        olmcs[0].tri_con = Some(Term { line_num: 1, rows: vec![vec![3]] });
+        Blueprint { chip: Chip::GAL22V10, sig: Vec::new(), olmcs, ar: None, sp: None }
+    }
+
+    #[test]
+    fn a_faithful_build_has_no_divergence() {
+        let blueprint = make_blueprint(Term { line_num: 1, rows: vec![vec![1, -2]] });
+        let gal = gal_builder::build(&blueprint, false).unwrap();
+
+        assert!(verify(&blueprint, &gal, &[false; 10]).is_empty());
+    }
+
+    #[test]
+    fn a_blueprint_that_disagrees_with_the_built_gal_is_flagged() {
+        // Build the fuse map for `A AND !B`, then verify it against a
+        // blueprint that instead claims O0 should be `A AND B` - the
+        // built GAL can't match, so this must report a divergence.
+        let built_from = make_blueprint(Term { line_num: 1, rows: vec![vec![1, -2]] });
+        let gal = gal_builder::build(&built_from, false).unwrap();
+
+        let claimed = make_blueprint(Term { line_num: 1, rows: vec![vec![1, 2]] });
+        let divergences = verify(&claimed, &gal, &[false; 10]);
+
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].olmc, 0);
+    }
+}
@@ -50,7 +50,7 @@ fn test_regression_old_school() -> Result<()> {
     Command::new("sh")
         .args([
             "-c",
-            &format!("cp testcases_success/GAL16V8_combinatorial.pld test_tmp/security_bit.pld"),
+            "cp testcases_success/GAL16V8_combinatorial.pld test_tmp/security_bit.pld",
         ])
         .spawn()?
         .wait()?;
@@ -74,7 +74,7 @@ fn test_regression_old_school() -> Result<()> {
     names.sort();
 
     for name in names.iter() {
-        log_name(&name)?;
+        log_name(name)?;
 
         let log_file = OpenOptions::new()
             .append(true)
@@ -83,14 +83,14 @@ fn test_regression_old_school() -> Result<()> {
         let log_file2 = log_file.try_clone().unwrap();
 
         get_test_bin("galette")
-            .arg(&name)
+            .arg(name)
             .current_dir(TEST_TEMP_DIR)
             .stdout(log_file)
             .stderr(log_file2)
             .spawn()?
             .wait()?;
 
-        remove_file(&format!("{}/{}", TEST_TEMP_DIR, name))?;
+        remove_file(format!("{}/{}", TEST_TEMP_DIR, name))?;
     }
 
     let diff_res = Command::new("diff")
@@ -148,7 +148,7 @@ fn test_successful_generation() -> Result<()> {
             results.stderr
         );
 
-        remove_file(&format!("{}/{}", "test_temp_success", name))?;
+        remove_file(format!("{}/{}", "test_temp_success", name))?;
     }
 
     let diff_res = Command::new("diff")